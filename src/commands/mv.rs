@@ -3,6 +3,8 @@ use crate::errors::ShellError;
 use crate::parser::hir::SyntaxType;
 use crate::parser::registry::{CommandRegistry, Signature};
 use crate::prelude::*;
+use regex::{Captures, Regex};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 pub struct Move;
@@ -11,6 +13,7 @@ pub struct Move;
 pub struct MoveArgs {
     src: Tagged<PathBuf>,
     dst: Tagged<PathBuf>,
+    force: bool,
 }
 
 impl PerItemCommand for Move {
@@ -23,6 +26,11 @@ impl PerItemCommand for Move {
             .required("source", SyntaxType::Path)
             .required("destination", SyntaxType::Path)
             .named("file", SyntaxType::Any)
+            .switch(
+                "force",
+                "overwrite destinations that already exist outside the moved set",
+                Some('f'),
+            )
     }
 
     fn run(
@@ -36,8 +44,181 @@ impl PerItemCommand for Move {
     }
 }
 
+/// Turns a source glob pattern into a regex that captures each `*`/`?`
+/// positionally, so a destination template's `#1`, `#2`, ... can refer back
+/// to what was matched (mmv-style mass rename). Bracket classes
+/// (`[...]`/`[!...]`) are translated the same way `pattern::glob_to_regex`
+/// translates them for matching, just without capturing them, so the two
+/// engines can't diverge on what a source pattern matches.
+fn capture_regex_for_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut regex_str = String::from("^");
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' => {
+                regex_str.push_str("(.*?)");
+                i += 1;
+            }
+            '?' => {
+                regex_str.push_str("(.)");
+                i += 1;
+            }
+            '[' => match crate::pattern::parse_bracket_class(&chars, i) {
+                Some((fragment, next)) => {
+                    regex_str.push_str(&fragment);
+                    i = next;
+                }
+                None => {
+                    regex_str.push_str("\\[");
+                    i += 1;
+                }
+            },
+            ch => {
+                regex_str.push_str(&regex::escape(&ch.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    regex_str.push('$');
+    Regex::new(&regex_str)
+}
+
+fn has_wildcards(pattern: &str) -> bool {
+    pattern.contains('*') || pattern.contains('?')
+}
+
+/// True if `pattern` names more than one path component. The capture regex
+/// is matched against a bare file name (`entry.file_name()`), so a pattern
+/// with a directory component (e.g. `pics/*.jpg`) would otherwise never
+/// match anything.
+fn has_directory_component(pattern: &str) -> bool {
+    std::path::Path::new(pattern).components().count() > 1
+}
+
+fn has_capture_tokens(template: &str) -> bool {
+    CAPTURE_TOKEN.is_match(template)
+}
+
+lazy_static::lazy_static! {
+    static ref CAPTURE_TOKEN: Regex = Regex::new(r"#(\d+)").expect("valid capture token regex");
+}
+
+/// Validates a batch of planned renames before anything touches the
+/// filesystem: two sources can't collide on the same destination, and a
+/// destination that already exists outside the moved set requires
+/// `--force`. Collision errors are anchored at the conflicting source's own
+/// tag rather than the command's, so the span points at the responsible
+/// argument.
+fn validate_move_plan(moves: &[(PathBuf, PathBuf, Tag)], force: bool) -> Result<(), ShellError> {
+    let mut by_destination: HashMap<&PathBuf, Vec<&(PathBuf, PathBuf, Tag)>> = HashMap::new();
+    for entry @ (_, dst, _) in moves {
+        by_destination
+            .entry(dst)
+            .or_insert_with(Vec::new)
+            .push(entry);
+    }
+
+    for (dst, conflicts) in &by_destination {
+        if conflicts.len() > 1 {
+            let srcs: Vec<&PathBuf> = conflicts.iter().map(|(src, _, _)| src).collect();
+            let (_, _, first_tag) = conflicts[0];
+
+            return Err(ShellError::labeled_error(
+                format!(
+                    "Rename aborted. {:?} would be overwritten by more than one source: {:?}",
+                    dst, srcs
+                ),
+                format!("{:?} has more than one source mapping to it", dst),
+                first_tag,
+            ));
+        }
+    }
+
+    let planned_sources: HashSet<&PathBuf> = moves.iter().map(|(src, _, _)| src).collect();
+
+    for (_, dst, src_tag) in moves {
+        if dst.exists() && !planned_sources.contains(dst) && !force {
+            return Err(ShellError::labeled_error(
+                format!(
+                    "Rename aborted. {:?} already exists (use --force to overwrite)",
+                    dst
+                ),
+                format!("{:?} already exists", dst),
+                *src_tag,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Executes a validated batch of renames, favoring moves whose destination
+/// isn't itself a pending source. When every remaining move is part of a
+/// chain or cycle, the first one is routed through a temporary name so nothing
+/// gets clobbered mid-loop.
+fn execute_move_plan(
+    mut moves: Vec<(PathBuf, PathBuf, Tag)>,
+    name_span: &Tag,
+) -> Result<(), ShellError> {
+    let mut temp_counter = 0usize;
+
+    while !moves.is_empty() {
+        let pending_sources: HashSet<&PathBuf> = moves.iter().map(|(src, _, _)| src).collect();
+        let safe_index = moves
+            .iter()
+            .position(|(_, dst, _)| !pending_sources.contains(dst));
+
+        if let Some(index) = safe_index {
+            let (src, dst, _) = moves.remove(index);
+            rename_or_labeled_error(&src, &dst, name_span)?;
+        } else {
+            let (src, dst, src_tag) = moves.remove(0);
+            temp_counter += 1;
+            let temp = temp_path_for(&src, temp_counter);
+            rename_or_labeled_error(&src, &temp, name_span)?;
+            moves.push((temp, dst, src_tag));
+        }
+    }
+
+    Ok(())
+}
+
+fn temp_path_for(path: &PathBuf, counter: usize) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut temp = path.clone();
+    temp.set_file_name(format!(
+        ".mv-tmp-{}-{}-{}",
+        std::process::id(),
+        counter,
+        file_name
+    ));
+    temp
+}
+
+fn rename_or_labeled_error(
+    from: &PathBuf,
+    to: &PathBuf,
+    name_span: &Tag,
+) -> Result<(), ShellError> {
+    std::fs::rename(from, to).map_err(|e| {
+        ShellError::labeled_error_with_source(
+            format!("Rename {:?} to {:?} aborted", from, to),
+            format!("Rename {:?} to {:?} aborted", from, to),
+            name_span,
+            e,
+        )
+    })
+}
+
 fn mv(
-    MoveArgs { src, dst }: MoveArgs,
+    MoveArgs { src, dst, force }: MoveArgs,
     RunnablePerItemContext {
         name,
         shell_manager,
@@ -47,16 +228,162 @@ fn mv(
     let mut destination = dst.item.clone();
     let name_span = name;
 
-    let sources: Vec<_> = match glob::glob(&source.to_string_lossy()) {
-        Ok(files) => files.collect(),
-        Err(_) => {
-            return Err(ShellError::labeled_error(
-                "Invalid pattern.",
-                "Invalid pattern.",
-                src.tag,
-            ))
+    let sources: Vec<(PathBuf, Tag)> = crate::pattern::expand_pattern(&src)?;
+
+    let destination_template = destination.to_string_lossy().to_string();
+
+    if has_capture_tokens(&destination_template) {
+        let source_str = source.to_string_lossy().to_string();
+        let (source_syntax, source_pattern) = crate::pattern::split_syntax_prefix(&source_str);
+
+        // `glob:` captures each `*`/`?` positionally; `re:` captures whatever
+        // parenthesized groups the regex itself defines. Either way, the
+        // match is later run again against each source entry to recover the
+        // same captures for substitution.
+        let capture_regex = match source_syntax {
+            crate::pattern::PatternSyntax::Glob => {
+                if !has_wildcards(source_pattern) {
+                    return Err(ShellError::labeled_error(
+                        "Rename aborted. Destination references captures (#1, #2, ...), but the source pattern has no wildcards to capture",
+                        "destination has no matching wildcards in source",
+                        dst.span(),
+                    ));
+                }
+
+                if has_directory_component(source_pattern) {
+                    return Err(ShellError::labeled_error(
+                        "Rename aborted. Captures (#1, #2, ...) only support a source pattern naming files directly, not one with a directory component",
+                        "source pattern has a directory component",
+                        src.tag,
+                    ));
+                }
+
+                match capture_regex_for_pattern(source_pattern) {
+                    Ok(regex) => regex,
+                    Err(_) => {
+                        return Err(ShellError::labeled_error(
+                            "Invalid pattern.",
+                            "Invalid pattern.",
+                            src.tag,
+                        ))
+                    }
+                }
+            }
+            crate::pattern::PatternSyntax::Regex => {
+                let regex = match Regex::new(source_pattern) {
+                    Ok(regex) => regex,
+                    Err(_) => {
+                        return Err(ShellError::labeled_error(
+                            "Invalid pattern.",
+                            "Invalid pattern.",
+                            src.tag,
+                        ))
+                    }
+                };
+
+                if regex.captures_len() <= 1 {
+                    return Err(ShellError::labeled_error(
+                        "Rename aborted. Destination references captures (#1, #2, ...), but the source pattern has no (...) capture groups",
+                        "destination has no matching capture groups in source",
+                        dst.span(),
+                    ));
+                }
+
+                regex
+            }
+            crate::pattern::PatternSyntax::Literal => {
+                return Err(ShellError::labeled_error(
+                    "Rename aborted. Destination references captures (#1, #2, ...), which only number glob wildcards or re: capture groups; path: sources aren't supported here",
+                    "captures require a glob: or re: source pattern",
+                    src.tag,
+                ));
+            }
+        };
+
+        let substitute = |template: &str, captures: &Captures| -> Result<String, ShellError> {
+            let mut result = String::new();
+            let mut last_end = 0;
+
+            for token in CAPTURE_TOKEN.captures_iter(template) {
+                let whole = token.get(0).expect("capture 0 always present");
+                let index: usize = match token[1].parse() {
+                    Ok(index) => index,
+                    Err(_) => {
+                        return Err(ShellError::labeled_error(
+                            format!("Rename aborted. Capture index #{} is out of range", &token[1]),
+                            format!("capture index #{} is out of range", &token[1]),
+                            dst.span(),
+                        ))
+                    }
+                };
+
+                result.push_str(&template[last_end..whole.start()]);
+
+                match captures.get(index) {
+                    Some(capture) => result.push_str(capture.as_str()),
+                    None => {
+                        return Err(ShellError::labeled_error(
+                            format!(
+                            "Rename aborted. #{} has no matching capture in the source pattern",
+                            index
+                        ),
+                            format!("capture #{} not present", index),
+                            dst.span(),
+                        ))
+                    }
+                }
+
+                last_end = whole.end();
+            }
+
+            result.push_str(&template[last_end..]);
+
+            Ok(result)
+        };
+
+        let mut moves = Vec::new();
+
+        for (entry, entry_tag) in sources {
+            let entry_file_name = match entry.file_name() {
+                Some(name) => name,
+                None => {
+                    return Err(ShellError::labeled_error(
+                        "Rename aborted. Not a valid entry name",
+                        "Rename aborted. Not a valid entry name",
+                        name_span,
+                    ))
+                }
+            };
+
+            // `re:` patterns are matched against the whole (possibly
+            // multi-component) candidate text by `pattern::expand_pattern`,
+            // so captures need to be recovered the same way; `glob:`
+            // patterns are filename-only (directory components were
+            // rejected above).
+            let match_text = if source_syntax == crate::pattern::PatternSyntax::Regex {
+                entry.to_string_lossy()
+            } else {
+                entry_file_name.to_string_lossy()
+            };
+
+            let captures = match capture_regex.captures(&match_text) {
+                Some(captures) => captures,
+                None => continue,
+            };
+
+            let new_name = substitute(&destination_template, &captures)?;
+
+            let mut to = entry.clone();
+            to.set_file_name(&new_name);
+
+            moves.push((entry, to, entry_tag));
         }
-    };
+
+        validate_move_plan(&moves, force)?;
+        execute_move_plan(moves, name_span)?;
+
+        return Ok(VecDeque::new());
+    }
 
     if "." == destination.to_string_lossy() {
         destination = PathBuf::from(shell_manager.path());
@@ -76,174 +403,139 @@ fn mv(
     };
 
     if sources.len() == 1 {
-        if let Ok(entry) = &sources[0] {
-            let entry_file_name = match entry.file_name() {
-                Some(name) => name,
-                None => {
-                    return Err(ShellError::labeled_error(
-                        "Rename aborted. Not a valid entry name",
-                        "Rename aborted. Not a valid entry name",
+        let (entry, _entry_tag) = &sources[0];
+        let entry_file_name = match entry.file_name() {
+            Some(name) => name,
+            None => {
+                return Err(ShellError::labeled_error(
+                    "Rename aborted. Not a valid entry name",
+                    "Rename aborted. Not a valid entry name",
+                    name_span,
+                ))
+            }
+        };
+
+        if destination.exists() && destination.is_dir() {
+            destination = match dunce::canonicalize(&destination) {
+                Ok(path) => path,
+                Err(e) => {
+                    return Err(ShellError::labeled_error_with_source(
+                        "Rename aborted",
+                        "Rename aborted",
                         name_span,
+                        e,
                     ))
                 }
             };
 
-            if destination.exists() && destination.is_dir() {
-                destination = match dunce::canonicalize(&destination) {
-                    Ok(path) => path,
-                    Err(e) => {
-                        return Err(ShellError::labeled_error(
-                            format!("Rename aborted. {:}", e.to_string()),
-                            format!("Rename aborted. {:}", e.to_string()),
-                            name_span,
-                        ))
-                    }
-                };
+            destination.push(entry_file_name);
+        }
 
-                destination.push(entry_file_name);
-            }
+        if entry.is_file() {
+            match std::fs::rename(&entry, &destination) {
+                Err(e) => {
+                    return Err(ShellError::labeled_error_with_source(
+                        format!(
+                            "Rename {:?} to {:?} aborted",
+                            entry_file_name, destination_file_name
+                        ),
+                        format!(
+                            "Rename {:?} to {:?} aborted",
+                            entry_file_name, destination_file_name
+                        ),
+                        name_span,
+                        e,
+                    ));
+                }
+                Ok(o) => o,
+            };
+        }
 
-            if entry.is_file() {
+        if entry.is_dir() {
+            match std::fs::create_dir_all(&destination) {
+                Err(e) => {
+                    return Err(ShellError::labeled_error_with_source(
+                        format!(
+                            "Rename {:?} to {:?} aborted",
+                            entry_file_name, destination_file_name
+                        ),
+                        format!(
+                            "Rename {:?} to {:?} aborted",
+                            entry_file_name, destination_file_name
+                        ),
+                        name_span,
+                        e,
+                    ));
+                }
+                Ok(o) => o,
+            };
+            #[cfg(not(windows))]
+            {
                 match std::fs::rename(&entry, &destination) {
                     Err(e) => {
-                        return Err(ShellError::labeled_error(
+                        return Err(ShellError::labeled_error_with_source(
                             format!(
-                                "Rename {:?} to {:?} aborted. {:}",
-                                entry_file_name,
-                                destination_file_name,
-                                e.to_string(),
+                                "Rename {:?} to {:?} aborted",
+                                entry_file_name, destination_file_name
                             ),
                             format!(
-                                "Rename {:?} to {:?} aborted. {:}",
-                                entry_file_name,
-                                destination_file_name,
-                                e.to_string(),
+                                "Rename {:?} to {:?} aborted",
+                                entry_file_name, destination_file_name
                             ),
                             name_span,
+                            e,
                         ));
                     }
                     Ok(o) => o,
                 };
             }
+            #[cfg(windows)]
+            {
+                use crate::utils::FileStructure;
 
-            if entry.is_dir() {
-                match std::fs::create_dir_all(&destination) {
-                    Err(e) => {
-                        return Err(ShellError::labeled_error(
-                            format!(
-                                "Rename {:?} to {:?} aborted. {:}",
-                                entry_file_name,
-                                destination_file_name,
-                                e.to_string(),
-                            ),
-                            format!(
-                                "Rename {:?} to {:?} aborted. {:}",
-                                entry_file_name,
-                                destination_file_name,
-                                e.to_string(),
-                            ),
-                            name_span,
-                        ));
-                    }
-                    Ok(o) => o,
-                };
-                #[cfg(not(windows))]
-                {
-                    match std::fs::rename(&entry, &destination) {
-                        Err(e) => {
-                            return Err(ShellError::labeled_error(
-                                format!(
-                                    "Rename {:?} to {:?} aborted. {:}",
-                                    entry_file_name,
-                                    destination_file_name,
-                                    e.to_string(),
-                                ),
-                                format!(
-                                    "Rename {:?} to {:?} aborted. {:}",
-                                    entry_file_name,
-                                    destination_file_name,
-                                    e.to_string(),
-                                ),
-                                name_span,
-                            ));
-                        }
-                        Ok(o) => o,
-                    };
-                }
-                #[cfg(windows)]
-                {
-                    use crate::utils::FileStructure;
+                let mut sources: FileStructure = FileStructure::new();
 
-                    let mut sources: FileStructure = FileStructure::new();
+                sources.walk_decorate(&entry)?;
 
-                    sources.walk_decorate(&entry)?;
+                let strategy = |(source_file, depth_level)| {
+                    let mut new_dst = destination.clone();
 
-                    let strategy = |(source_file, depth_level)| {
-                        let mut new_dst = destination.clone();
+                    let path = dunce::canonicalize(&source_file)?;
 
-                        let path = dunce::canonicalize(&source_file)?;
+                    let mut comps: Vec<_> = path
+                        .components()
+                        .map(|fragment| fragment.as_os_str())
+                        .rev()
+                        .take(1 + depth_level)
+                        .collect();
 
-                        let mut comps: Vec<_> = path
-                            .components()
-                            .map(|fragment| fragment.as_os_str())
-                            .rev()
-                            .take(1 + depth_level)
-                            .collect();
+                    comps.reverse();
 
-                        comps.reverse();
+                    for fragment in comps.iter() {
+                        new_dst.push(fragment);
+                    }
 
-                        for fragment in comps.iter() {
-                            new_dst.push(fragment);
-                        }
+                    Ok((PathBuf::from(&source_file), PathBuf::from(new_dst)))
+                };
 
-                        Ok((PathBuf::from(&source_file), PathBuf::from(new_dst)))
-                    };
-
-                    let sources = sources.paths_applying_with(strategy)?;
-
-                    for (ref src, ref dst) in sources {
-                        if src.is_dir() {
-                            if !dst.exists() {
-                                match std::fs::create_dir_all(dst) {
-                                    Err(e) => {
-                                        return Err(ShellError::labeled_error(
-                                            format!(
-                                                "Rename {:?} to {:?} aborted. {:}",
-                                                entry_file_name,
-                                                destination_file_name,
-                                                e.to_string(),
-                                            ),
-                                            format!(
-                                                "Rename {:?} to {:?} aborted. {:}",
-                                                entry_file_name,
-                                                destination_file_name,
-                                                e.to_string(),
-                                            ),
-                                            name_span,
-                                        ));
-                                    }
-                                    Ok(o) => o,
-                                }
-                            }
-                        }
+                let sources = sources.paths_applying_with(strategy)?;
 
-                        if src.is_file() {
-                            match std::fs::rename(src, dst) {
+                for (ref src, ref dst) in sources {
+                    if src.is_dir() {
+                        if !dst.exists() {
+                            match std::fs::create_dir_all(dst) {
                                 Err(e) => {
-                                    return Err(ShellError::labeled_error(
+                                    return Err(ShellError::labeled_error_with_source(
                                         format!(
-                                            "Rename {:?} to {:?} aborted. {:}",
-                                            entry_file_name,
-                                            destination_file_name,
-                                            e.to_string(),
+                                            "Rename {:?} to {:?} aborted",
+                                            entry_file_name, destination_file_name
                                         ),
                                         format!(
-                                            "Rename {:?} to {:?} aborted. {:}",
-                                            entry_file_name,
-                                            destination_file_name,
-                                            e.to_string(),
+                                            "Rename {:?} to {:?} aborted",
+                                            entry_file_name, destination_file_name
                                         ),
                                         name_span,
+                                        e,
                                     ));
                                 }
                                 Ok(o) => o,
@@ -251,93 +543,146 @@ fn mv(
                         }
                     }
 
-                    match std::fs::remove_dir_all(entry) {
-                        Err(e) => {
-                            return Err(ShellError::labeled_error(
-                                format!(
-                                    "Rename {:?} to {:?} aborted. {:}",
-                                    entry_file_name,
-                                    destination_file_name,
-                                    e.to_string(),
-                                ),
-                                format!(
-                                    "Rename {:?} to {:?} aborted. {:}",
-                                    entry_file_name,
-                                    destination_file_name,
-                                    e.to_string(),
-                                ),
-                                name_span,
-                            ));
-                        }
-                        Ok(o) => o,
-                    };
-                }
-            }
-        }
-    } else {
-        if destination.exists() {
-            if !sources.iter().all(|x| {
-                if let Ok(entry) = x.as_ref() {
-                    entry.is_file()
-                } else {
-                    false
-                }
-            }) {
-                return Err(ShellError::labeled_error(
-                    "Rename aborted (directories found). Renaming in patterns not supported yet (try moving the directory directly)",
-                    "Rename aborted (directories found). Renaming in patterns not supported yet (try moving the directory directly)",
-                    src.tag,
-                ));
-            }
-
-            for entry in sources {
-                if let Ok(entry) = entry {
-                    let entry_file_name = match entry.file_name() {
-                        Some(name) => name,
-                        None => {
-                            return Err(ShellError::labeled_error(
-                                "Rename aborted. Not a valid entry name",
-                                "Rename aborted. Not a valid entry name",
-                                name_span,
-                            ))
-                        }
-                    };
-
-                    let mut to = PathBuf::from(&destination);
-                    to.push(entry_file_name);
-
-                    if entry.is_file() {
-                        match std::fs::rename(&entry, &to) {
+                    if src.is_file() {
+                        match std::fs::rename(src, dst) {
                             Err(e) => {
-                                return Err(ShellError::labeled_error(
+                                return Err(ShellError::labeled_error_with_source(
                                     format!(
-                                        "Rename {:?} to {:?} aborted. {:}",
-                                        entry_file_name,
-                                        destination_file_name,
-                                        e.to_string(),
+                                        "Rename {:?} to {:?} aborted",
+                                        entry_file_name, destination_file_name
                                     ),
                                     format!(
-                                        "Rename {:?} to {:?} aborted. {:}",
-                                        entry_file_name,
-                                        destination_file_name,
-                                        e.to_string(),
+                                        "Rename {:?} to {:?} aborted",
+                                        entry_file_name, destination_file_name
                                     ),
                                     name_span,
+                                    e,
                                 ));
                             }
                             Ok(o) => o,
-                        };
+                        }
                     }
                 }
+
+                match std::fs::remove_dir_all(entry) {
+                    Err(e) => {
+                        return Err(ShellError::labeled_error_with_source(
+                            format!(
+                                "Rename {:?} to {:?} aborted",
+                                entry_file_name, destination_file_name
+                            ),
+                            format!(
+                                "Rename {:?} to {:?} aborted",
+                                entry_file_name, destination_file_name
+                            ),
+                            name_span,
+                            e,
+                        ));
+                    }
+                    Ok(o) => o,
+                };
             }
-        } else {
+        }
+    } else if destination.exists() {
+        if !sources.iter().all(|(entry, _)| entry.is_file()) {
             return Err(ShellError::labeled_error(
-                format!("Rename aborted. (Does {:?} exist?)", destination_file_name),
-                format!("Rename aborted. (Does {:?} exist?)", destination_file_name),
-                dst.span(),
+                "Rename aborted (directories found). Renaming in patterns not supported yet (try moving the directory directly)",
+                "Rename aborted (directories found). Renaming in patterns not supported yet (try moving the directory directly)",
+                src.tag,
             ));
         }
+
+        let mut moves = Vec::new();
+
+        for (entry, entry_tag) in sources {
+            let entry_file_name = match entry.file_name() {
+                Some(name) => name,
+                None => {
+                    return Err(ShellError::labeled_error(
+                        "Rename aborted. Not a valid entry name",
+                        "Rename aborted. Not a valid entry name",
+                        name_span,
+                    ))
+                }
+            };
+
+            let mut to = PathBuf::from(&destination);
+            to.push(entry_file_name);
+
+            moves.push((entry, to, entry_tag));
+        }
+
+        validate_move_plan(&moves, force)?;
+        execute_move_plan(moves, name_span)?;
+    } else {
+        return Err(ShellError::labeled_error(
+            format!("Rename aborted. (Does {:?} exist?)", destination_file_name),
+            format!("Rename aborted. (Does {:?} exist?)", destination_file_name),
+            dst.span(),
+        ));
     }
 
     Ok(VecDeque::new())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_directory_component() {
+        assert!(!has_directory_component("*.txt"));
+        assert!(has_directory_component("pics/*.jpg"));
+        assert!(has_directory_component("pics/sub/*.jpg"));
+    }
+
+    #[test]
+    fn capture_regex_matches_and_numbers_wildcards() {
+        let regex = capture_regex_for_pattern("img_*_*.png").unwrap();
+        let captures = regex.captures("img_a_b.png").unwrap();
+
+        assert_eq!(&captures[1], "a");
+        assert_eq!(&captures[2], "b");
+    }
+
+    #[test]
+    fn capture_regex_matches_bracket_class_alongside_wildcards() {
+        let regex = capture_regex_for_pattern("img_[0-9]_*.png").unwrap();
+        let captures = regex.captures("img_5_a.png").unwrap();
+
+        assert_eq!(&captures[1], "a");
+        assert!(!regex.is_match("img_x_a.png"));
+    }
+
+    #[test]
+    fn validate_move_plan_rejects_destination_collisions() {
+        let tag = Tag::unknown();
+        let moves = vec![
+            (PathBuf::from("a.txt"), PathBuf::from("c.txt"), tag),
+            (PathBuf::from("b.txt"), PathBuf::from("c.txt"), tag),
+        ];
+
+        assert!(validate_move_plan(&moves, false).is_err());
+    }
+
+    #[test]
+    fn execute_move_plan_breaks_swap_cycles_with_a_temp_name() {
+        let dir = std::env::temp_dir().join(format!("nu-mv-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create test dir");
+
+        let a = dir.join("a.txt");
+        let b = dir.join("b.txt");
+        std::fs::write(&a, b"a").expect("write a");
+        std::fs::write(&b, b"b").expect("write b");
+
+        let tag = Tag::unknown();
+        let moves = vec![(a.clone(), b.clone(), tag), (b.clone(), a.clone(), tag)];
+
+        execute_move_plan(moves, &tag).expect("swap cycle should resolve");
+
+        assert_eq!(std::fs::read(&a).unwrap(), b"b");
+        assert_eq!(std::fs::read(&b).unwrap(), b"a");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
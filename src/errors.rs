@@ -0,0 +1,111 @@
+use crate::prelude::*;
+use std::fmt;
+
+/// A structured shell error: a user-facing message and label anchored to a
+/// span, plus an optional chain of underlying causes (e.g. the `io::Error`
+/// that triggered it) so callers don't have to flatten everything into a
+/// string up front.
+#[derive(Debug)]
+pub struct ShellError {
+    msg: String,
+    label: String,
+    span: Tag,
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+}
+
+impl ShellError {
+    pub fn labeled_error(
+        msg: impl Into<String>,
+        label: impl Into<String>,
+        span: impl Into<Tag>,
+    ) -> ShellError {
+        ShellError {
+            msg: msg.into(),
+            label: label.into(),
+            span: span.into(),
+            source: None,
+        }
+    }
+
+    /// Like `labeled_error`, but keeps the original error attached instead of
+    /// flattening it with `to_string()`. Preserves things like an
+    /// `io::Error`'s `kind()`/errno and any cause it wraps, so callers can
+    /// still match on them and the rendered error can show the full chain.
+    pub fn labeled_error_with_source(
+        msg: impl Into<String>,
+        label: impl Into<String>,
+        span: impl Into<Tag>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> ShellError {
+        ShellError {
+            msg: msg.into(),
+            label: label.into(),
+            span: span.into(),
+            source: Some(Box::new(source)),
+        }
+    }
+
+    pub fn tag(&self) -> Tag {
+        self.span
+    }
+}
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.msg, self.label)?;
+
+        let mut cause = self
+            .source
+            .as_deref()
+            .map(|err| err as &(dyn std::error::Error));
+
+        while let Some(err) = cause {
+            write!(f, "\ncaused by: {}", err)?;
+            cause = err.source();
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ShellError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|err| err as &(dyn std::error::Error + 'static))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn display_without_a_source_has_no_caused_by() {
+        let err = ShellError::labeled_error("Rename aborted", "not found", Tag::unknown());
+        let rendered = err.to_string();
+
+        assert_eq!(rendered, "Rename aborted: not found");
+        assert!(!rendered.contains("caused by:"));
+    }
+
+    #[test]
+    fn display_walks_the_source_chain() {
+        let io_err = io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "permission denied (os error 13)",
+        );
+        let err = ShellError::labeled_error_with_source(
+            "Rename aborted",
+            "Rename aborted",
+            Tag::unknown(),
+            io_err,
+        );
+        let rendered = err.to_string();
+
+        assert!(rendered.contains("Rename aborted: Rename aborted"));
+        assert!(rendered.contains("caused by: permission denied (os error 13)"));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
@@ -0,0 +1,346 @@
+//! A shared `glob:`/`re:`/`path:` pattern engine for file-path arguments.
+//! Currently wired into `mv` only; `cp`, `rm`, and `ls` don't exist yet in
+//! this tree, so they aren't consumers here, but this module has no
+//! `mv`-specific logic and is meant to be shared once they land.
+
+use crate::errors::ShellError;
+use crate::prelude::*;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// The syntax a path argument's pattern should be interpreted with, selected
+/// by an optional leading prefix (`glob:`, `re:`, `path:`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternSyntax {
+    /// Shell glob syntax (the default): `*`, `?`, `**`.
+    Glob,
+    /// A regular expression matched against each candidate path.
+    Regex,
+    /// A literal path, matched with no expansion at all.
+    Literal,
+}
+
+/// Splits a path argument into its syntax prefix and the remaining pattern
+/// text. Defaults to `Glob` when no recognized prefix is present, so callers
+/// that don't opt in keep today's behavior unchanged.
+pub fn split_syntax_prefix(raw: &str) -> (PatternSyntax, &str) {
+    if let Some(rest) = raw.strip_prefix("re:") {
+        (PatternSyntax::Regex, rest)
+    } else if let Some(rest) = raw.strip_prefix("path:") {
+        (PatternSyntax::Literal, rest)
+    } else if let Some(rest) = raw.strip_prefix("glob:") {
+        (PatternSyntax::Glob, rest)
+    } else {
+        (PatternSyntax::Glob, raw)
+    }
+}
+
+/// Parses a glob bracket character class (`[abc]`, `[!abc]`) starting at
+/// `chars[i]` (which must be `[`), returning the translated regex fragment
+/// and the index just past the closing `]`. Returns `None` when there's no
+/// closing `]`, in which case the `[` should be treated as a literal
+/// character instead.
+pub(crate) fn parse_bracket_class(chars: &[char], i: usize) -> Option<(String, usize)> {
+    let mut j = i + 1;
+    let mut negate = false;
+
+    if chars.get(j) == Some(&'!') {
+        negate = true;
+        j += 1;
+    }
+
+    let class_start = j;
+
+    // A `]` immediately after `[` or `[!` is a literal member, not the
+    // closing bracket (standard glob bracket-class convention).
+    if chars.get(j) == Some(&']') {
+        j += 1;
+    }
+
+    while j < chars.len() && chars[j] != ']' {
+        j += 1;
+    }
+
+    if j >= chars.len() {
+        return None;
+    }
+
+    let mut fragment = String::from("[");
+    if negate {
+        fragment.push('^');
+    }
+
+    for &c in &chars[class_start..j] {
+        if c == '\\' || c == '^' || c == ']' {
+            fragment.push('\\');
+        }
+        fragment.push(c);
+    }
+
+    fragment.push(']');
+
+    Some((fragment, j + 1))
+}
+
+/// Translates a glob pattern into an equivalent anchored regex, so `glob:`
+/// and `re:` patterns can be matched through the same engine. Regex
+/// metacharacters and whitespace in literal runs are escaped; `*/`, `**` and
+/// `*` are then translated to their directory-aware regex equivalents, `?`
+/// to a single-character match, and `[...]`/`[!...]` to a regex character
+/// class.
+pub fn glob_to_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut regex_str = String::from("^");
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                regex_str.push_str(".*");
+                i += 2;
+            }
+            '*' if chars.get(i + 1) == Some(&'/') => {
+                regex_str.push_str("(?:.*/)?");
+                i += 2;
+            }
+            '*' => {
+                regex_str.push_str("[^/]*");
+                i += 1;
+            }
+            '?' => {
+                regex_str.push_str("[^/]");
+                i += 1;
+            }
+            '[' => match parse_bracket_class(&chars, i) {
+                Some((fragment, next)) => {
+                    regex_str.push_str(&fragment);
+                    i = next;
+                }
+                None => {
+                    regex_str.push_str("\\[");
+                    i += 1;
+                }
+            },
+            c => {
+                if "()[]{}+-|^$\\.&~#".contains(c) || c.is_whitespace() {
+                    regex_str.push('\\');
+                }
+                regex_str.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    regex_str.push('$');
+    Regex::new(&regex_str)
+}
+
+/// Collects every path under `dir`, reported as `text_prefix` joined with
+/// the path relative to `dir`, as candidates for `regex` to match against.
+/// `text_prefix` mirrors the literal (non-wildcard) prefix of the original
+/// pattern, so a candidate's textual form lines up with what the pattern's
+/// regex expects. Only descends into subdirectories when `recursive` is set
+/// (the pattern contained `**`) and never follows symlinked directories, so
+/// a lone `*` stays within one directory level and a symlink cycle can't
+/// send this into unbounded recursion.
+fn collect_candidates(dir: &Path, text_prefix: &Path, recursive: bool, out: &mut Vec<PathBuf>) {
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.filter_map(Result::ok) {
+            let candidate = text_prefix.join(entry.file_name());
+
+            out.push(candidate.clone());
+
+            if recursive {
+                if let Ok(file_type) = entry.file_type() {
+                    if file_type.is_dir() {
+                        collect_candidates(&entry.path(), &candidate, recursive, out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// True if `pattern` contains `**`, meaning a match may need to recurse into
+/// subdirectories; a lone `*` only matches within a single directory level.
+fn needs_recursion(pattern: &str) -> bool {
+    pattern.contains("**")
+}
+
+/// The literal (non-wildcard) leading path components of a pattern, used to
+/// both find where to start walking the filesystem and to keep candidate
+/// paths textually aligned with the pattern's regex.
+fn literal_prefix(pattern: &str) -> PathBuf {
+    let mut prefix = PathBuf::new();
+
+    for component in Path::new(pattern).components() {
+        let piece = component.as_os_str().to_string_lossy();
+
+        if piece.contains('*') || piece.contains('?') || piece.contains('[') {
+            break;
+        }
+
+        prefix.push(component.as_os_str());
+    }
+
+    prefix
+}
+
+/// Decides where and how to walk the filesystem for a pattern: the
+/// directory to start `read_dir`-ing from, the text prefix to join onto
+/// each candidate, and whether to recurse into subdirectories. `pattern`'s
+/// literal (non-wildcard) prefix is only a meaningful starting directory for
+/// `Glob` syntax; a `Regex` pattern's text isn't made of path components at
+/// all (e.g. `^IMG_(\d+)\.JPG$`), so it always walks recursively from `.`
+/// instead of misreading the regex source as a path. `Literal` never reaches
+/// here: `expand_pattern` resolves it with a direct existence check before
+/// any call into `matching_entries`/`walk_plan`, since "matched with no
+/// expansion at all" means there's no filesystem walk to plan.
+fn walk_plan(syntax: PatternSyntax, pattern: &str) -> (PathBuf, PathBuf, bool) {
+    match syntax {
+        PatternSyntax::Regex => (PathBuf::from("."), PathBuf::new(), true),
+        PatternSyntax::Glob => {
+            let prefix = literal_prefix(pattern);
+            let walk_root = if prefix.as_os_str().is_empty() {
+                PathBuf::from(".")
+            } else {
+                prefix.clone()
+            };
+
+            (walk_root, prefix, needs_recursion(pattern))
+        }
+        PatternSyntax::Literal => unreachable!(
+            "expand_pattern resolves Literal patterns directly, without calling walk_plan"
+        ),
+    }
+}
+
+fn matching_entries(syntax: PatternSyntax, pattern: &str, regex: &Regex) -> Vec<PathBuf> {
+    let (walk_root, prefix, recursive) = walk_plan(syntax, pattern);
+
+    let mut candidates = Vec::new();
+    collect_candidates(&walk_root, &prefix, recursive, &mut candidates);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| regex.is_match(&candidate.to_string_lossy()))
+        .collect()
+}
+
+/// True if `pattern` contains a glob metacharacter (`*`, `?`, `[`) that needs
+/// expansion against the filesystem, as opposed to naming a single literal
+/// path directly.
+fn has_glob_metachars(pattern: &str) -> bool {
+    pattern.contains('*') || pattern.contains('?') || pattern.contains('[')
+}
+
+/// Expands a tagged path argument into its matching paths, honoring the
+/// argument's syntax prefix (defaulting to `glob:`). Errors are labeled
+/// against `raw`'s tag, so callers don't need to build their own. Each
+/// returned path is paired with `raw`'s tag, so callers can anchor
+/// downstream errors (e.g. destination collisions) at the source argument.
+pub fn expand_pattern(raw: &Tagged<PathBuf>) -> Result<Vec<(PathBuf, Tag)>, ShellError> {
+    let raw_str = raw.item.to_string_lossy().to_string();
+    let (syntax, pattern) = split_syntax_prefix(&raw_str);
+
+    if let PatternSyntax::Glob = syntax {
+        if !has_glob_metachars(pattern) {
+            let path = PathBuf::from(pattern);
+
+            return Ok(if path.exists() {
+                vec![(path, raw.tag)]
+            } else {
+                vec![]
+            });
+        }
+    }
+
+    let regex = match syntax {
+        PatternSyntax::Glob => glob_to_regex(pattern),
+        PatternSyntax::Regex => Regex::new(pattern),
+        PatternSyntax::Literal => {
+            let path = PathBuf::from(pattern);
+
+            return Ok(if path.exists() {
+                vec![(path, raw.tag)]
+            } else {
+                vec![]
+            });
+        }
+    };
+
+    match regex {
+        Ok(regex) => Ok(matching_entries(syntax, pattern, &regex)
+            .into_iter()
+            .map(|path| (path, raw.tag))
+            .collect()),
+        Err(_) => Err(ShellError::labeled_error(
+            "Invalid pattern.",
+            "Invalid pattern.",
+            raw.tag,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_glob_metachars() {
+        assert!(!has_glob_metachars("foo.txt"));
+        assert!(has_glob_metachars("*.txt"));
+        assert!(has_glob_metachars("foo?.txt"));
+        assert!(has_glob_metachars("foo[0-9].txt"));
+    }
+
+    #[test]
+    fn split_syntax_prefix_defaults_to_glob() {
+        assert_eq!(split_syntax_prefix("*.txt"), (PatternSyntax::Glob, "*.txt"));
+        assert_eq!(split_syntax_prefix("re:^a$"), (PatternSyntax::Regex, "^a$"));
+        assert_eq!(
+            split_syntax_prefix("path:a/b"),
+            (PatternSyntax::Literal, "a/b")
+        );
+    }
+
+    #[test]
+    fn glob_to_regex_translates_bracket_classes() {
+        let re = glob_to_regex("img_[0-9].png").unwrap();
+        assert!(re.is_match("img_5.png"));
+        assert!(!re.is_match("img_a.png"));
+
+        let re = glob_to_regex("img_[!0-9].png").unwrap();
+        assert!(!re.is_match("img_5.png"));
+        assert!(re.is_match("img_a.png"));
+    }
+
+    #[test]
+    fn glob_to_regex_treats_unterminated_bracket_as_literal() {
+        let re = glob_to_regex("a[b.txt").unwrap();
+        assert!(re.is_match("a[b.txt"));
+    }
+
+    #[test]
+    fn only_double_star_needs_recursion() {
+        assert!(!needs_recursion("*.txt"));
+        assert!(!needs_recursion("sub/*.txt"));
+        assert!(needs_recursion("**/*.txt"));
+    }
+
+    #[test]
+    fn regex_syntax_walks_from_current_dir_instead_of_the_pattern_text() {
+        let (root, prefix, recursive) = walk_plan(PatternSyntax::Regex, r"^IMG_(\d+)\.JPG$");
+        assert_eq!(root, PathBuf::from("."));
+        assert_eq!(prefix, PathBuf::new());
+        assert!(recursive);
+    }
+
+    #[test]
+    fn glob_syntax_still_uses_the_literal_prefix() {
+        let (root, prefix, recursive) = walk_plan(PatternSyntax::Glob, "sub/*.txt");
+        assert_eq!(root, PathBuf::from("sub"));
+        assert_eq!(prefix, PathBuf::from("sub"));
+        assert!(!recursive);
+    }
+}